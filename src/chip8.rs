@@ -2,7 +2,7 @@ extern crate rand;
 
 use std::sync::mpmc::{Receiver, Sender};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use log::{error, info};
 use rand::Rng;
@@ -11,6 +11,35 @@ const SCREEN_WIDTH: usize = 64;
 const SCREEN_HEIGHT: usize = 32;
 const SCREEN_SIZE: usize = SCREEN_WIDTH * SCREEN_HEIGHT;
 
+// delay/sound timers always tick down at 60 Hz, independent of the CPU
+// instruction rate.
+const TIMER_HZ: u32 = 60;
+const TIMER_INTERVAL: Duration = Duration::from_nanos(1_000_000_000 / TIMER_HZ as u64);
+
+// default CPU instruction rate; configurable via `with_instruction_rate`.
+const DEFAULT_INSTRUCTION_HZ: u32 = 700;
+
+// the standard CHIP-8 hex font, 5 bytes (4x5 pixels) per glyph, 0x0-0xF in order.
+// conventionally loaded into low memory starting at 0x000.
+const FONT_SET: [u8; 80] = [
+    0xF0, 0x90, 0x90, 0x90, 0xF0, // 0
+    0x20, 0x60, 0x20, 0x20, 0x70, // 1
+    0xF0, 0x10, 0xF0, 0x80, 0xF0, // 2
+    0xF0, 0x10, 0xF0, 0x10, 0xF0, // 3
+    0x90, 0x90, 0xF0, 0x10, 0x10, // 4
+    0xF0, 0x80, 0xF0, 0x10, 0xF0, // 5
+    0xF0, 0x80, 0xF0, 0x90, 0xF0, // 6
+    0xF0, 0x10, 0x20, 0x40, 0x40, // 7
+    0xF0, 0x90, 0xF0, 0x90, 0xF0, // 8
+    0xF0, 0x90, 0xF0, 0x10, 0xF0, // 9
+    0xF0, 0x90, 0xF0, 0x90, 0x90, // A
+    0xE0, 0x90, 0xE0, 0x90, 0xE0, // B
+    0xF0, 0x80, 0x80, 0x80, 0xF0, // C
+    0xE0, 0x90, 0x90, 0x90, 0xE0, // D
+    0xF0, 0x80, 0xF0, 0x80, 0xF0, // E
+    0xF0, 0x80, 0xF0, 0x80, 0x80, // F
+];
+
 #[derive(PartialEq, Eq)]
 enum SystemState {
     Quit,
@@ -18,6 +47,147 @@ enum SystemState {
     Paused,
 }
 
+/// Commands accepted by a paused `System` over its debug control channel.
+pub enum DebugCommand {
+    /// Execute exactly one CPU cycle, then remain paused.
+    Step,
+    /// Resume normal execution.
+    Continue,
+    /// Halt execution after the current cycle.
+    Pause,
+}
+
+/// Splits an opcode into its four nibbles, most significant first.
+fn decode(opcode: u16) -> (u8, u8, u8, u8) {
+    (
+        ((opcode & 0xF000) >> 12) as u8,
+        ((opcode & 0x0F00) >> 8) as u8,
+        ((opcode & 0x00F0) >> 4) as u8,
+        (opcode & 0x000F) as u8,
+    )
+}
+
+/// The lowest 12 bits of an opcode, used by instructions that take an address.
+fn nnn(opcode: u16) -> u16 {
+    opcode & 0x0FFF
+}
+
+/// The lowest 8 bits of an opcode, used by instructions that take a byte literal.
+fn kk(opcode: u16) -> u8 {
+    (opcode & 0x00FF) as u8
+}
+
+/// Renders an opcode as a human-readable mnemonic, e.g. `DRW V2, V3, 5`.
+pub fn disassemble(opcode: u16) -> String {
+    let (op, x, y, n) = decode(opcode);
+    let addr = nnn(opcode);
+    let byte = kk(opcode);
+
+    match op {
+        0x0 => match opcode {
+            0x00E0 => "CLS".to_string(),
+            0x00EE => "RET".to_string(),
+            _ => format!("DW {opcode:#06X}"),
+        },
+        0x1 => format!("JP {addr:#05X}"),
+        0x2 => format!("CALL {addr:#05X}"),
+        0x3 => format!("SE V{x:X}, {byte:#04X}"),
+        0x4 => format!("SNE V{x:X}, {byte:#04X}"),
+        0x5 if n == 0 => format!("SE V{x:X}, V{y:X}"),
+        0x6 => format!("LD V{x:X}, {byte:#04X}"),
+        0x7 => format!("ADD V{x:X}, {byte:#04X}"),
+        0x8 => match n {
+            0x0 => format!("LD V{x:X}, V{y:X}"),
+            0x1 => format!("OR V{x:X}, V{y:X}"),
+            0x2 => format!("AND V{x:X}, V{y:X}"),
+            0x3 => format!("XOR V{x:X}, V{y:X}"),
+            0x4 => format!("ADD V{x:X}, V{y:X}"),
+            0x5 => format!("SUB V{x:X}, V{y:X}"),
+            0x6 => format!("SHR V{x:X}, V{y:X}"),
+            0x7 => format!("SUBN V{x:X}, V{y:X}"),
+            0xE => format!("SHL V{x:X}, V{y:X}"),
+            _ => format!("DW {opcode:#06X}"),
+        },
+        0x9 if n == 0 => format!("SNE V{x:X}, V{y:X}"),
+        0xA => format!("LD I, {addr:#05X}"),
+        0xB => format!("JP V0, {addr:#05X}"),
+        0xC => format!("RND V{x:X}, {byte:#04X}"),
+        0xD => format!("DRW V{x:X}, V{y:X}, {n}"),
+        0xE => match byte {
+            0x9E => format!("SKP V{x:X}"),
+            0xA1 => format!("SKNP V{x:X}"),
+            _ => format!("DW {opcode:#06X}"),
+        },
+        0xF => match byte {
+            0x07 => format!("LD V{x:X}, DT"),
+            0x0A => format!("LD V{x:X}, K"),
+            0x15 => format!("LD DT, V{x:X}"),
+            0x18 => format!("LD ST, V{x:X}"),
+            0x1E => format!("ADD I, V{x:X}"),
+            0x29 => format!("LD F, V{x:X}"),
+            0x33 => format!("LD B, V{x:X}"),
+            0x55 => format!("LD [I], V{x:X}"),
+            0x65 => format!("LD V{x:X}, [I]"),
+            _ => format!("DW {opcode:#06X}"),
+        },
+        _ => format!("DW {opcode:#06X}"),
+    }
+}
+
+/// Selects between the handful of opcode behaviors that differ across
+/// CHIP-8 interpreter generations. ROMs are usually written against one
+/// specific profile and misbehave under the others.
+#[derive(Clone, Copy)]
+pub struct Quirks {
+    /// 8XY6/8XYE shift V[y] (copied into V[x]) instead of shifting V[x] in place.
+    pub shift_uses_vy: bool,
+    /// FX55/FX65 advance `i` by `x + 1` after the store/load loop.
+    pub index_increment_on_store_load: bool,
+    /// BNNN jumps to XNN + V[x] instead of NNN + V[0].
+    pub bxnn_uses_vx: bool,
+    /// 8XY1/8XY2/8XY3 (OR/AND/XOR) reset V[0xF] to 0.
+    pub vf_reset_on_logic: bool,
+    /// DXYN clips sprite pixels that fall past the right/bottom edge of the
+    /// screen instead of wrapping them to the opposite side.
+    pub clip_sprites: bool,
+}
+
+impl Quirks {
+    /// Original COSMAC VIP behavior.
+    pub const fn cosmac_vip() -> Self {
+        Self {
+            shift_uses_vy: true,
+            index_increment_on_store_load: true,
+            bxnn_uses_vx: false,
+            vf_reset_on_logic: true,
+            clip_sprites: true,
+        }
+    }
+
+    /// CHIP-48 / SUPER-CHIP behavior.
+    pub const fn chip48() -> Self {
+        Self {
+            shift_uses_vy: false,
+            index_increment_on_store_load: false,
+            bxnn_uses_vx: true,
+            vf_reset_on_logic: false,
+            clip_sprites: true,
+        }
+    }
+
+    /// SUPER-CHIP behavior. Shares the same register-op quirks as CHIP-48;
+    /// the two differ in opcodes (e.g. scrolling) not modeled by this struct.
+    pub const fn super_chip() -> Self {
+        Self::chip48()
+    }
+}
+
+impl Default for Quirks {
+    fn default() -> Self {
+        Self::cosmac_vip()
+    }
+}
+
 pub struct System {
     state: SystemState,
     memory: [u8; 4096],
@@ -31,11 +201,13 @@ pub struct System {
     keys: [bool; 16],
     gfx: [bool; SCREEN_SIZE],
     redraw_required: bool,
+    instruction_hz: u32,
+    quirks: Quirks,
 }
 
 impl System {
     pub fn new() -> Self {
-        return Self {
+        let mut system = Self {
             state: SystemState::Quit,
             memory: [0; 4096],
             v: [0; 16],
@@ -48,7 +220,24 @@ impl System {
             keys: [false; 16],
             gfx: [false; SCREEN_SIZE],
             redraw_required: true,
+            instruction_hz: DEFAULT_INSTRUCTION_HZ,
+            quirks: Quirks::default(),
         };
+        system.memory[0..0x50].copy_from_slice(&FONT_SET);
+        return system;
+    }
+
+    // overrides the default CPU instruction rate (the delay/sound timers
+    // always tick at a fixed 60 Hz regardless of this setting).
+    pub fn with_instruction_rate(mut self, instruction_hz: u32) -> Self {
+        self.instruction_hz = instruction_hz;
+        self
+    }
+
+    // selects which generation of CHIP-8 opcode quirks this system emulates.
+    pub fn with_quirks(mut self, quirks: Quirks) -> Self {
+        self.quirks = quirks;
+        self
     }
 
     fn reset<'a>(&'a mut self) {
@@ -64,9 +253,17 @@ impl System {
         self.keys = [false; 16];
         self.gfx = [false; SCREEN_SIZE];
         self.redraw_required = true;
+        self.memory[0..0x50].copy_from_slice(&FONT_SET);
     }
 
-    pub fn run<'a>(&'a mut self, tx_draw: &Sender<[bool; SCREEN_SIZE]>, rx_quit: &Receiver<bool>) {
+    pub fn run<'a>(
+        &'a mut self,
+        tx_draw: &Sender<[bool; SCREEN_SIZE]>,
+        rx_quit: &Receiver<bool>,
+        rx_keys: &Receiver<(u8, bool)>,
+        tx_beep: &Sender<bool>,
+        rx_debug: &Receiver<DebugCommand>,
+    ) {
         self.state = SystemState::Running;
 
         // TODO: remove debug pixels
@@ -75,9 +272,45 @@ impl System {
         self.gfx[SCREEN_WIDTH * SCREEN_HEIGHT - SCREEN_WIDTH] = true;
         self.gfx[SCREEN_SIZE - 1] = true;
 
-        while self.state == SystemState::Running {
+        let cycle_duration = Duration::from_secs_f64(1.0 / self.instruction_hz as f64);
+        let mut last_tick = Instant::now();
+        let mut timer_accumulator = Duration::ZERO;
+        let mut sound_playing = false;
+
+        while self.state != SystemState::Quit {
             if let Ok(_) = rx_quit.try_recv() {
                 self.state = SystemState::Quit;
+                break;
+            }
+
+            let mut step_once = false;
+            match rx_debug.try_recv() {
+                Ok(DebugCommand::Pause) => self.state = SystemState::Paused,
+                Ok(DebugCommand::Continue) => self.state = SystemState::Running,
+                Ok(DebugCommand::Step) => step_once = true,
+                Err(_) => {}
+            }
+
+            if self.state == SystemState::Paused && !step_once {
+                thread::sleep(Duration::from_millis(10));
+                continue;
+            }
+
+            self.drain_key_events(rx_keys);
+
+            let now = Instant::now();
+            timer_accumulator += now.duration_since(last_tick);
+            last_tick = now;
+            while timer_accumulator >= TIMER_INTERVAL {
+                self.tick_timers();
+                timer_accumulator -= TIMER_INTERVAL;
+            }
+
+            // beep for as long as the sound timer is non-zero
+            let should_play = self.sound_timer > 0;
+            if should_play != sound_playing {
+                tx_beep.send(should_play).unwrap();
+                sound_playing = should_play;
             }
 
             self.cycle();
@@ -87,6 +320,25 @@ impl System {
                 tx_draw.send(self.gfx.clone()).unwrap();
                 self.redraw_required = false;
             }
+
+            if !step_once {
+                thread::sleep(cycle_duration);
+            }
+        }
+    }
+
+    fn tick_timers<'a>(&'a mut self) {
+        if self.delay_timer > 0 {
+            self.delay_timer -= 1;
+        }
+        if self.sound_timer > 0 {
+            self.sound_timer -= 1;
+        }
+    }
+
+    fn drain_key_events<'a>(&'a mut self, rx_keys: &Receiver<(u8, bool)>) {
+        while let Ok((key, pressed)) = rx_keys.try_recv() {
+            self.keys[key as usize] = pressed;
         }
     }
 
@@ -101,25 +353,27 @@ impl System {
         let hi = self.memory[self.pc as usize + 1];
         let opcode = (lo as u16) << 8 | hi as u16;
         let pc = self.pc;
-        info!("PC: {pc:#06X}\nOP: {opcode:#06X}");
-
-        let opcode_valid = match opcode & 0xF000 {
-            0x0000 => self.op_0xxx(opcode),
-            0x1000 => self.op_1xxx(opcode),
-            0x2000 => self.op_2xxx(opcode),
-            0x3000 => self.op_3xxx(opcode),
-            0x4000 => self.op_4xxx(opcode),
-            0x5000 => self.op_5xxx(opcode),
-            0x6000 => self.op_6xxx(opcode),
-            0x7000 => self.op_7xxx(opcode),
-            0x8000 => self.op_8xxx(opcode),
-            0x9000 => self.op_9xxx(opcode),
-            0xa000 => self.op_axxx(opcode),
-            0xb000 => self.op_bxxx(opcode),
-            0xc000 => self.op_cxxx(opcode),
-            0xd000 => self.op_dxxx(opcode),
-            0xe000 => self.op_exxx(opcode),
-            0xf000 => self.op_fxxx(opcode),
+        let mnemonic = disassemble(opcode);
+        info!("PC: {pc:#06X}  OP: {opcode:#06X}  {mnemonic}  I: {:#06X}  V: {:02X?}", self.i, self.v);
+
+        let (op, ..) = decode(opcode);
+        let opcode_valid = match op {
+            0x0 => self.op_0xxx(opcode),
+            0x1 => self.op_1xxx(opcode),
+            0x2 => self.op_2xxx(opcode),
+            0x3 => self.op_3xxx(opcode),
+            0x4 => self.op_4xxx(opcode),
+            0x5 => self.op_5xxx(opcode),
+            0x6 => self.op_6xxx(opcode),
+            0x7 => self.op_7xxx(opcode),
+            0x8 => self.op_8xxx(opcode),
+            0x9 => self.op_9xxx(opcode),
+            0xa => self.op_axxx(opcode),
+            0xb => self.op_bxxx(opcode),
+            0xc => self.op_cxxx(opcode),
+            0xd => self.op_dxxx(opcode),
+            0xe => self.op_exxx(opcode),
+            0xf => self.op_fxxx(opcode),
             _ => false,
         };
 
@@ -127,8 +381,6 @@ impl System {
             let address = self.pc;
             error!("Invalid opcode {opcode:#06x} at address {address:#06x}");
         }
-
-        thread::sleep(Duration::from_millis(500));
     }
 
     fn op_0xxx<'a>(&'a mut self, opcode: u16) -> bool {
@@ -142,18 +394,32 @@ impl System {
                 self.next_instruction();
                 true
             }
-            0x00EE => false,
+            0x00EE => {
+                // 00EE: return from subroutine
+                if self.sp == 0 {
+                    error!("Stack underflow on RET at address {:#06x}", self.pc);
+                    return false;
+                }
+                self.sp -= 1;
+                self.pc = self.stack[self.sp as usize];
+                self.next_instruction();
+                true
+            }
             _ => false,
         };
     }
     fn op_1xxx<'a>(&'a mut self, opcode: u16) -> bool {
         // 1NNN: jump to address NNN
-        self.pc = opcode & 0x0FFF;
+        self.pc = nnn(opcode);
         true
     }
     fn op_2xxx<'a>(&'a mut self, opcode: u16) -> bool {
         // 2NNN: call subroutine at NNN
-        let address = opcode & 0x0FFF;
+        if self.sp as usize >= self.stack.len() {
+            error!("Stack overflow on CALL at address {:#06x}", self.pc);
+            return false;
+        }
+        let address = nnn(opcode);
         self.stack[self.sp as usize] = self.pc;
         self.sp += 1;
         self.pc = address;
@@ -161,8 +427,8 @@ impl System {
     }
     fn op_3xxx<'a>(&'a mut self, opcode: u16) -> bool {
         // 3XNN: skip the next instruction if v[x] equals NN
-        let x = ((opcode & 0x0F00) >> 8) as u8;
-        if self.v[x as usize] == (opcode & 0x00FF) as u8 {
+        let (_, x, _, _) = decode(opcode);
+        if self.v[x as usize] == kk(opcode) {
             self.next_instruction();
         }
         self.next_instruction();
@@ -170,8 +436,8 @@ impl System {
     }
     fn op_4xxx<'a>(&'a mut self, opcode: u16) -> bool {
         // 4XNN: skip the next instruction if v[x] does not equal NN
-        let x = ((opcode & 0x0F00) >> 8) as u8;
-        if self.v[x as usize] != (opcode & 0x00FF) as u8 {
+        let (_, x, _, _) = decode(opcode);
+        if self.v[x as usize] != kk(opcode) {
             self.next_instruction();
         }
         self.next_instruction();
@@ -179,8 +445,7 @@ impl System {
     }
     fn op_5xxx<'a>(&'a mut self, opcode: u16) -> bool {
         // 5XY0: skip the next instruction if v[x] equals v[y]
-        let x = ((opcode & 0x0F00) >> 8) as u8;
-        let y = ((opcode & 0x00F0) >> 4) as u8;
+        let (_, x, y, _) = decode(opcode);
         if x == y {
             self.next_instruction();
         }
@@ -189,92 +454,110 @@ impl System {
     }
     fn op_6xxx<'a>(&'a mut self, opcode: u16) -> bool {
         // 6XNN: set v[x] to NN
-        let x = ((opcode & 0x0F00) >> 8) as u8;
-        self.v[x as usize] = (opcode & 0x00FF) as u8;
+        let (_, x, _, _) = decode(opcode);
+        self.v[x as usize] = kk(opcode);
         self.next_instruction();
         true
     }
     fn op_7xxx<'a>(&'a mut self, opcode: u16) -> bool {
         // 7XNN: add NN to v[x]. does not change carry flag
-        let x = ((opcode & 0x0F00) >> 8) as u8;
-        self.v[x as usize] = self.v[x as usize] + ((opcode & 0x00FF) as u8 & 0xFF);
+        let (_, x, _, _) = decode(opcode);
+        self.v[x as usize] = self.v[x as usize].wrapping_add(kk(opcode));
         self.next_instruction();
         true
     }
     fn op_8xxx<'a>(&'a mut self, opcode: u16) -> bool {
-        let x = ((opcode & 0x0F00) >> 8) as u8 as usize;
-        let y = ((opcode & 0x00F0) >> 4) as u8 as usize;
+        let (_, x, y, n) = decode(opcode);
+        let x = x as usize;
+        let y = y as usize;
 
-        return match opcode & 0x000F {
-            0x0000 => {
+        return match n {
+            0x0 => {
                 // 0x8XY0: set v[x] to v[y]
                 self.v[x] = self.v[y];
                 self.next_instruction();
                 true
             }
-            0x0001 => {
+            0x1 => {
                 // 0x8XY1: set v[x] to (v[x] | v[y])
                 self.v[x] = self.v[x] | self.v[y];
+                if self.quirks.vf_reset_on_logic {
+                    self.v[0xF] = 0;
+                }
                 self.next_instruction();
                 true
             }
-            0x0002 => {
+            0x2 => {
                 // 0x8XY2: set v[x] to (v[x] & v[y])
                 self.v[x] = self.v[x] & self.v[y];
+                if self.quirks.vf_reset_on_logic {
+                    self.v[0xF] = 0;
+                }
                 self.next_instruction();
                 true
             }
-            0x0003 => {
+            0x3 => {
                 // 0x8XY3: set v[x] to (v[x] ^ v[y])
                 self.v[x] = self.v[x] ^ self.v[y];
+                if self.quirks.vf_reset_on_logic {
+                    self.v[0xF] = 0;
+                }
                 self.next_instruction();
                 true
             }
-            0x0004 => {
+            0x4 => {
                 // 0x8XY4: add v[y] to v[x]. set v[0xF] to 1 when overflow happened and to 0 when not
                 if self.v[x] > 255 - self.v[y] {
                     self.v[0xF] = 1;
                 } else {
                     self.v[0xF] = 0;
                 }
-                self.v[x] += self.v[y];
+                self.v[x] = self.v[x].wrapping_add(self.v[y]);
                 self.next_instruction();
                 true
             }
-            0x0005 => {
+            0x5 => {
                 // 0x8XY5: subtract v[y] from v[x]. set v[0xF] to 0 when underflow happened and to 1 when not
                 if self.v[x] < self.v[y] {
                     self.v[0xF] = 0;
                 } else {
                     self.v[0xF] = 1;
                 }
-                self.v[x] -= self.v[y];
+                self.v[x] = self.v[x].wrapping_sub(self.v[y]);
                 self.next_instruction();
                 true
             }
-            0x0006 => {
+            0x6 => {
                 // 0x8XY6: shift v[x] to the right by 1 then store least significant bit of v[x]
-                // prior to the shift into v[0xF]
+                // prior to the shift into v[0xF]. under `shift_uses_vy`, v[y] is copied into
+                // v[x] before shifting.
+                if self.quirks.shift_uses_vy {
+                    self.v[x] = self.v[y];
+                }
                 self.v[0xF] = self.v[x] & 0x01;
                 self.v[x] = self.v[x] >> 1;
                 self.next_instruction();
                 true
             }
-            0x0007 => {
+            0x7 => {
                 // 0x8XY7: set v[x] to (v[y] - v[x]). set v[0xF] to 1 when underflow happened and to 0 when not
                 if self.v[y] < self.v[x] {
                     self.v[0xF] = 0;
                 } else {
                     self.v[0xF] = 1;
                 }
-                self.v[x] = self.v[y] - self.v[x];
+                self.v[x] = self.v[y].wrapping_sub(self.v[x]);
                 self.next_instruction();
                 true
             }
-            0x000E => {
+            0xE => {
                 // 0x8XYE: shift v[x] to the left by 1 then set v[0xF] to 1 if the
                 // most significant bit of v[x] prior to the shift was set
-                // or else set v[0xF] to 0
+                // or else set v[0xF] to 0. under `shift_uses_vy`, v[y] is copied
+                // into v[x] before shifting.
+                if self.quirks.shift_uses_vy {
+                    self.v[x] = self.v[y];
+                }
                 if self.v[x] >> 7 == 1 {
                     self.v[0xF] = 1;
                 } else {
@@ -293,8 +576,9 @@ impl System {
         }
 
         // 9XY0: skip the next instruction if v[x] does not equal v[y]
-        let x = ((opcode & 0x0F00) >> 8) as u8 as usize;
-        let y = ((opcode & 0x00F0) >> 4) as u8 as usize;
+        let (_, x, y, _) = decode(opcode);
+        let x = x as usize;
+        let y = y as usize;
 
         if self.v[x] == self.v[y] {
             self.next_instruction();
@@ -306,46 +590,65 @@ impl System {
     }
     fn op_axxx<'a>(&'a mut self, opcode: u16) -> bool {
         // ANNN set i to the address NNN
-        self.i = opcode & 0x0FFF;
+        self.i = nnn(opcode);
         self.next_instruction();
         true
     }
     fn op_bxxx<'a>(&'a mut self, opcode: u16) -> bool {
-        // BNNN: jump to the address NNN plus v[0x0]
-        self.i = (opcode & 0x0FFF) + self.v[0] as u16;
-        self.next_instruction();
+        // BNNN: jump to the address NNN plus v[0x0]. under `bxnn_uses_vx`
+        // (CHIP-48/SUPER-CHIP), jump to XNN plus v[x] instead.
+        let (_, x, _, _) = decode(opcode);
+        let offset = if self.quirks.bxnn_uses_vx {
+            self.v[x as usize] as u16
+        } else {
+            self.v[0] as u16
+        };
+        self.pc = nnn(opcode) + offset;
         true
     }
     fn op_cxxx<'a>(&'a mut self, opcode: u16) -> bool {
         // CXNN: set v[x] to the result of (random_u8() & nn)
-        let x = ((opcode & 0x0F00) >> 8) as u8 as usize;
-        let nn = (opcode & 0x00FF) as u8;
-        self.v[x] = random_u8() & nn;
+        let (_, x, _, _) = decode(opcode);
+        self.v[x as usize] = random_u8() & kk(opcode);
         self.next_instruction();
         true
     }
     fn op_dxxx<'a>(&'a mut self, opcode: u16) -> bool {
-        // DXYN: draw sprite at coordinate (v[x], v[y]) that is 8xN pixels in size
-        let start_x = ((opcode & 0x0F00) >> 8) as u8;
-        let start_y = ((opcode & 0x00F0) >> 4) as u8;
-        let height = (opcode & 0x000F) as u8;
+        // DXYN: draw sprite at coordinate (v[x], v[y]) that is 8xN pixels in size.
+        // the starting coordinates wrap into range, but pixels that would fall past
+        // the right/bottom edge are clipped (not wrapped) when `clip_sprites` is set.
+        let (_, x, y, n) = decode(opcode);
+        let start_x = self.v[x as usize] as usize % SCREEN_WIDTH;
+        let start_y = self.v[y as usize] as usize % SCREEN_HEIGHT;
+        let height = n as usize;
 
         self.v[0xF] = 0;
 
-        for y in 0..height {
-            let line = self.memory[self.i as usize + y as usize];
-            for x in 0..8 {
-                let pixel = line & (0x80 >> x);
-                if pixel != 0 {
-                    let total_x = start_x + x;
-                    let total_y = start_y + y;
-                    let index = (total_y as usize * SCREEN_WIDTH) + total_x as usize;
-
-                    if self.gfx[index] {
-                        self.v[0xF] = 1;
-                    }
-                    self.gfx[index] = !self.gfx[index]; // is this correct?
+        for row in 0..height {
+            let total_y = start_y + row;
+            if total_y >= SCREEN_HEIGHT && self.quirks.clip_sprites {
+                break;
+            }
+            let draw_y = total_y % SCREEN_HEIGHT;
+            let line = self.memory[self.i as usize + row];
+
+            for col in 0..8usize {
+                let pixel = line & (0x80 >> col);
+                if pixel == 0 {
+                    continue;
                 }
+
+                let total_x = start_x + col;
+                if total_x >= SCREEN_WIDTH && self.quirks.clip_sprites {
+                    continue;
+                }
+                let draw_x = total_x % SCREEN_WIDTH;
+                let index = draw_y * SCREEN_WIDTH + draw_x;
+
+                if self.gfx[index] {
+                    self.v[0xF] = 1;
+                }
+                self.gfx[index] = !self.gfx[index];
             }
         }
 
@@ -354,11 +657,12 @@ impl System {
         true
     }
     fn op_exxx<'a>(&'a mut self, opcode: u16) -> bool {
-        let x = ((opcode & 0x0F00) >> 8) as u8 as usize;
+        let (_, x, _, _) = decode(opcode);
+        let x = x as usize;
         let key = self.keys[(self.v[x] & 0x0F) as usize];
 
-        return match opcode & 0x00FF {
-            0x009E => {
+        return match kk(opcode) {
+            0x9E => {
                 // EX9E: skip the next instruction if key stored in v[x]
                 // (only lowest nibble) is pressed
                 if key {
@@ -367,7 +671,7 @@ impl System {
                 self.next_instruction();
                 true
             }
-            0x00A1 => {
+            0xA1 => {
                 // EXA1: skip the next instruction if key stored in v[x]
                 // (only lowest nibble) is not pressed
                 if !key {
@@ -380,45 +684,48 @@ impl System {
         };
     }
     fn op_fxxx<'a>(&'a mut self, opcode: u16) -> bool {
-        let x = ((opcode & 0x0F00) >> 8) as u8 as usize;
+        let (_, x, _, _) = decode(opcode);
+        let x = x as usize;
 
-        return match opcode & 0x00FF {
-            0x0007 => {
+        return match kk(opcode) {
+            0x07 => {
                 // FX0A: set v[x] to the value of the delay timer
                 self.v[x] = self.delay_timer;
                 self.next_instruction();
                 true
             }
-            0x000A => {
+            0x0A => {
                 // TODO: FX15: wait for key press and store it in v[x].
                 // this is a blocking operation. halt all instructions
                 // until next key event. timers should continue processing
                 false
             }
-            0x0015 => {
+            0x15 => {
                 self.delay_timer = self.v[x];
                 self.next_instruction();
                 true
             }
-            0x0018 => {
+            0x18 => {
                 // FX18: set the sound timer to the value of v[x]
                 self.sound_timer = self.v[x];
                 self.next_instruction();
                 true
             }
-            0x001E => {
+            0x1E => {
                 // FX1E: add v[x] to i (v[0xF] is not affected)
                 self.i += self.v[x] as u16;
                 self.next_instruction();
                 true
             }
-            0x0029 => {
+            0x29 => {
                 // FX29: set i to the location of the sprite for the character
                 // v[x] (only consider lowest nibble).
                 // characters 0x0-0xF are represented by a 4x5 font
-                false
+                self.i = (self.v[x] & 0x0F) as u16 * 5;
+                self.next_instruction();
+                true
             }
-            0x0033 => {
+            0x33 => {
                 // FX33: store the binary-coded decimal representation of v[x]
                 // with the hundreds digit in memory at location i.
                 // the tens digit at location i+1 and the ones digit at i+2
@@ -431,21 +738,29 @@ impl System {
                 self.next_instruction();
                 true
             }
-            0x0055 => {
+            0x55 => {
                 // FX55: store registers v[0x0] to v[x] (including v[x]) in memory
-                // starting at address i
-                for offset in 0..self.v[x] as usize {
+                // starting at address i. under `index_increment_on_store_load`, i
+                // advances by x+1 afterwards.
+                for offset in 0..=x {
                     self.memory[self.i as usize + offset] = self.v[offset];
                 }
+                if self.quirks.index_increment_on_store_load {
+                    self.i += (x + 1) as u16;
+                }
                 self.next_instruction();
                 true
             }
-            0x0065 => {
+            0x65 => {
                 // FX65: fill registers v[0x0] to v[x] (including v[x]) with values
-                // from memory starting at address i
-                for offset in 0..self.v[x] as usize {
+                // from memory starting at address i. under `index_increment_on_store_load`,
+                // i advances by x+1 afterwards.
+                for offset in 0..=x {
                     self.v[offset] = self.memory[self.i as usize + offset];
                 }
+                if self.quirks.index_increment_on_store_load {
+                    self.i += (x + 1) as u16;
+                }
                 self.next_instruction();
                 true
             }
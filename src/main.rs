@@ -4,12 +4,13 @@ mod chip8;
 
 extern crate sdl3;
 
-use std::sync::mpmc::{Receiver, channel};
+use std::sync::mpmc::{Receiver, Sender, channel};
 use std::time::Duration;
 use std::{env, fs, process, thread};
 
-use chip8::System;
+use chip8::{DebugCommand, System};
 
+use sdl3::audio::{AudioCallback, AudioSpecDesired};
 use sdl3::event::Event;
 use sdl3::keyboard::Keycode;
 
@@ -19,11 +20,67 @@ use sdl3::sys::pixels::SDL_PixelFormat;
 use sdl3::sys::render::SDL_SetTextureScaleMode;
 use sdl3::sys::surface::SDL_SCALEMODE_NEAREST;
 
+// beep waveform tuning, used by `SquareWave`.
+const BEEP_FREQUENCY_HZ: f32 = 440.0;
+const BEEP_VOLUME: f32 = 0.25;
+
+struct SquareWave {
+    phase_inc: f32,
+    phase: f32,
+    volume: f32,
+}
+
+impl AudioCallback for SquareWave {
+    type Channel = f32;
+
+    fn callback(&mut self, out: &mut [f32]) {
+        for sample in out.iter_mut() {
+            *sample = if self.phase <= 0.5 {
+                self.volume
+            } else {
+                -self.volume
+            };
+            self.phase = (self.phase + self.phase_inc) % 1.0;
+        }
+    }
+}
+
+// maps host keycodes onto the CHIP-8 hex keypad using the conventional
+// 1234/QWER/ASDF/ZXCV layout.
+const KEYMAP: [(Keycode, u8); 16] = [
+    (Keycode::Num1, 0x1),
+    (Keycode::Num2, 0x2),
+    (Keycode::Num3, 0x3),
+    (Keycode::Num4, 0xC),
+    (Keycode::Q, 0x4),
+    (Keycode::W, 0x5),
+    (Keycode::E, 0x6),
+    (Keycode::R, 0xD),
+    (Keycode::A, 0x7),
+    (Keycode::S, 0x8),
+    (Keycode::D, 0x9),
+    (Keycode::F, 0xE),
+    (Keycode::Z, 0xA),
+    (Keycode::X, 0x0),
+    (Keycode::C, 0xB),
+    (Keycode::V, 0xF),
+];
+
+fn keycode_to_chip8_key(keycode: Keycode) -> Option<u8> {
+    KEYMAP
+        .iter()
+        .find(|(kc, _)| *kc == keycode)
+        .map(|(_, key)| *key)
+}
+
 fn main() {
     colog::init();
 
     let (tx_draw, rx_draw) = channel::<[bool; 64 * 32]>();
     let (tx_quit, rx_quit) = channel::<bool>();
+    let (tx_keys, rx_keys) = channel::<(u8, bool)>();
+    let (tx_beep, rx_beep) = channel::<bool>();
+    let (tx_debug, rx_debug) = channel::<DebugCommand>();
 
     // read args
     let args: Vec<String> = env::args().collect();
@@ -49,8 +106,9 @@ fn main() {
 
     let ui_rx_quit = rx_quit.clone();
     let ui_rx_draw = rx_draw.clone();
+    let ui_tx_keys = tx_keys.clone();
     let ui_handle = thread::spawn(move || {
-        init_ui(&ui_rx_quit, &ui_rx_draw).unwrap_or_else(|e| {
+        init_ui(&ui_rx_quit, &ui_rx_draw, &ui_tx_keys, &rx_beep, &tx_debug).unwrap_or_else(|e| {
             panic!("Failed to initialize window: {e:?}");
         });
     });
@@ -58,7 +116,7 @@ fn main() {
     let system_rx_quit = rx_quit.clone();
     let system_tx_draw = tx_draw.clone();
     let system_handle = thread::spawn(move || {
-        system.run(&system_tx_draw, &system_rx_quit);
+        system.run(&system_tx_draw, &system_rx_quit, &rx_keys, &tx_beep, &rx_debug);
     });
 
     // wait for ui thread to finish
@@ -74,9 +132,27 @@ fn main() {
     tx_quit.send(true).unwrap();
 }
 
-fn init_ui(rx_quit: &Receiver<bool>, rx_draw: &Receiver<[bool; 64 * 32]>) -> Result<(), String> {
+fn init_ui(
+    rx_quit: &Receiver<bool>,
+    rx_draw: &Receiver<[bool; 64 * 32]>,
+    tx_keys: &Sender<(u8, bool)>,
+    rx_beep: &Receiver<bool>,
+    tx_debug: &Sender<DebugCommand>,
+) -> Result<(), String> {
     let sdl_context = sdl3::init()?;
     let video_subsystem = sdl_context.video()?;
+    let audio_subsystem = sdl_context.audio()?;
+
+    let audio_spec = AudioSpecDesired {
+        freq: Some(44_100),
+        channels: Some(1),
+        samples: None,
+    };
+    let audio_device = audio_subsystem.open_playback(None, &audio_spec, |spec| SquareWave {
+        phase_inc: BEEP_FREQUENCY_HZ / spec.freq as f32,
+        phase: 0.0,
+        volume: BEEP_VOLUME,
+    })?;
 
     let window = video_subsystem
         .window("Nic's CHIP-8 Emulator", 640, 320)
@@ -116,6 +192,15 @@ fn init_ui(rx_quit: &Receiver<bool>, rx_draw: &Receiver<[bool; 64 * 32]>) -> Res
             canvas.present();
         }
 
+        // mute/unmute the beep as the sound timer starts and stops
+        if let Ok(playing) = rx_beep.try_recv() {
+            if playing {
+                audio_device.resume();
+            } else {
+                audio_device.pause();
+            }
+        }
+
         // handle quit events
         if let Ok(_) = rx_quit.recv_timeout(Duration::from_millis(50)) {
             break 'mainloop;
@@ -131,6 +216,44 @@ fn init_ui(rx_quit: &Receiver<bool>, rx_draw: &Receiver<[bool; 64 * 32]>) -> Res
                 } => {
                     break 'mainloop;
                 }
+                Event::KeyDown {
+                    keycode: Option::Some(Keycode::F9),
+                    repeat: false,
+                    ..
+                } => {
+                    tx_debug.send(DebugCommand::Pause).unwrap();
+                }
+                Event::KeyDown {
+                    keycode: Option::Some(Keycode::F10),
+                    repeat: false,
+                    ..
+                } => {
+                    tx_debug.send(DebugCommand::Step).unwrap();
+                }
+                Event::KeyDown {
+                    keycode: Option::Some(Keycode::F5),
+                    repeat: false,
+                    ..
+                } => {
+                    tx_debug.send(DebugCommand::Continue).unwrap();
+                }
+                Event::KeyDown {
+                    keycode: Option::Some(keycode),
+                    repeat: false,
+                    ..
+                } => {
+                    if let Some(key) = keycode_to_chip8_key(keycode) {
+                        tx_keys.send((key, true)).unwrap();
+                    }
+                }
+                Event::KeyUp {
+                    keycode: Option::Some(keycode),
+                    ..
+                } => {
+                    if let Some(key) = keycode_to_chip8_key(keycode) {
+                        tx_keys.send((key, false)).unwrap();
+                    }
+                }
                 _ => {}
             }
         }
@@ -139,6 +262,8 @@ fn init_ui(rx_quit: &Receiver<bool>, rx_draw: &Receiver<[bool; 64 * 32]>) -> Res
     drop(texture);
     drop(texture_creator);
     drop(canvas);
+    drop(audio_device);
+    drop(audio_subsystem);
     drop(video_subsystem);
     drop(sdl_context);
 